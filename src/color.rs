@@ -0,0 +1,245 @@
+//! ANSI color constants and shell-aware zero-width escaping
+//!
+//! Bash and Zsh miscompute prompt width when non-printing escape sequences
+//! aren't marked as zero-width, causing line-wrap and cursor corruption on
+//! long prompts. [`wrap_escape`] marks each escape run using the shell's own
+//! delimiters so only the visible glyphs count toward its width calculation.
+
+pub const RESET: &str = "\x1b[0m";
+
+pub const BLACK: &str = "\x1b[30m";
+pub const RED: &str = "\x1b[31m";
+pub const GREEN: &str = "\x1b[32m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const BLUE: &str = "\x1b[34m";
+pub const PURPLE: &str = "\x1b[35m";
+pub const CYAN: &str = "\x1b[36m";
+pub const WHITE: &str = "\x1b[37m";
+pub const BRIGHT_BLACK: &str = "\x1b[90m";
+pub const BRIGHT_RED: &str = "\x1b[91m";
+pub const BRIGHT_GREEN: &str = "\x1b[92m";
+pub const BRIGHT_YELLOW: &str = "\x1b[93m";
+pub const BRIGHT_BLUE: &str = "\x1b[94m";
+pub const BRIGHT_MAGENTA: &str = "\x1b[95m";
+pub const BRIGHT_CYAN: &str = "\x1b[96m";
+pub const BRIGHT_WHITE: &str = "\x1b[97m";
+
+/// Shell the prompt is rendered for, which determines how escape sequences
+/// must be marked zero-width so the shell's line editor doesn't miscount
+/// prompt width
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShellType {
+    Bash,
+    Zsh,
+    #[default]
+    Other,
+}
+
+impl ShellType {
+    /// Detect the shell from a raw value such as `$SHELL`, matching on its basename
+    #[must_use]
+    pub fn detect(shell_env: Option<&str>) -> Self {
+        match shell_env.and_then(|s| s.rsplit('/').next()) {
+            Some("bash") => ShellType::Bash,
+            Some("zsh") => ShellType::Zsh,
+            _ => ShellType::Other,
+        }
+    }
+}
+
+/// Wrap a single ANSI escape run (e.g. `BLUE` or `RESET`) in `shell`'s
+/// non-printing delimiters. `Other` leaves the escape raw, as before.
+#[must_use]
+pub fn wrap_escape(escape: &str, shell: ShellType) -> String {
+    match shell {
+        ShellType::Bash => format!("\\[{escape}\\]"),
+        ShellType::Zsh => format!("%{{{escape}%}}"),
+        ShellType::Other => escape.to_string(),
+    }
+}
+
+/// A resolved color value - a named 16-color, an xterm 256-color index, or
+/// a truecolor RGB triple - each rendering to its own ANSI escape sequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Color {
+    Named(&'static str),
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// The ANSI escape sequence that sets this color as the foreground
+    #[must_use]
+    pub fn escape(&self) -> std::borrow::Cow<'static, str> {
+        match self {
+            Color::Named(code) => std::borrow::Cow::Borrowed(*code),
+            Color::Indexed(n) => std::borrow::Cow::Owned(format!("\x1b[38;5;{n}m")),
+            Color::Rgb(r, g, b) => std::borrow::Cow::Owned(format!("\x1b[38;2;{r};{g};{b}m")),
+        }
+    }
+}
+
+/// A color value string matched none of the supported syntaxes
+#[derive(Debug)]
+pub struct ParseColorError(String);
+
+impl std::fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid color value: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl std::str::FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Parse `#rrggbb` truecolor, `256:<n>` xterm-palette, or a named
+    /// 16-color (e.g. `blue`, `bright-magenta`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix('#') {
+            return parse_truecolor(hex).ok_or_else(|| ParseColorError(s.to_string()));
+        }
+        if let Some(index) = s.strip_prefix("256:") {
+            return index
+                .parse::<u8>()
+                .map(Color::Indexed)
+                .map_err(|_| ParseColorError(s.to_string()));
+        }
+        named_color(s).ok_or_else(|| ParseColorError(s.to_string()))
+    }
+}
+
+fn parse_truecolor(hex: &str) -> Option<Color> {
+    if hex.len() != 6 {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some(Color::Rgb(r, g, b))
+}
+
+fn named_color(name: &str) -> Option<Color> {
+    let code = match name.to_ascii_lowercase().as_str() {
+        "black" => BLACK,
+        "red" => RED,
+        "green" => GREEN,
+        "yellow" => YELLOW,
+        "blue" => BLUE,
+        "purple" | "magenta" => PURPLE,
+        "cyan" => CYAN,
+        "white" => WHITE,
+        "bright-black" | "bright_black" => BRIGHT_BLACK,
+        "bright-red" | "bright_red" => BRIGHT_RED,
+        "bright-green" | "bright_green" => BRIGHT_GREEN,
+        "bright-yellow" | "bright_yellow" => BRIGHT_YELLOW,
+        "bright-blue" | "bright_blue" => BRIGHT_BLUE,
+        "bright-purple" | "bright-magenta" | "bright_purple" | "bright_magenta" => BRIGHT_MAGENTA,
+        "bright-cyan" | "bright_cyan" => BRIGHT_CYAN,
+        "bright-white" | "bright_white" => BRIGHT_WHITE,
+        _ => return None,
+    };
+    Some(Color::Named(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_escape_bash_marks_zero_width() {
+        assert_eq!(wrap_escape(BLUE, ShellType::Bash), "\\[\x1b[34m\\]");
+    }
+
+    #[test]
+    fn test_wrap_escape_zsh_marks_zero_width() {
+        assert_eq!(wrap_escape(BLUE, ShellType::Zsh), "%{\x1b[34m%}");
+    }
+
+    #[test]
+    fn test_wrap_escape_other_leaves_raw() {
+        assert_eq!(wrap_escape(BLUE, ShellType::Other), BLUE);
+    }
+
+    #[test]
+    fn test_shell_type_detect_matches_basename() {
+        assert_eq!(ShellType::detect(Some("/bin/bash")), ShellType::Bash);
+        assert_eq!(ShellType::detect(Some("/usr/bin/zsh")), ShellType::Zsh);
+        assert_eq!(ShellType::detect(Some("/bin/fish")), ShellType::Other);
+        assert_eq!(ShellType::detect(None), ShellType::Other);
+    }
+
+    #[test]
+    fn test_shell_type_default_is_other() {
+        assert_eq!(ShellType::default(), ShellType::Other);
+    }
+
+    #[test]
+    fn test_parse_named_color() {
+        assert_eq!("blue".parse::<Color>().unwrap(), Color::Named(BLUE));
+        assert_eq!("RED".parse::<Color>().unwrap(), Color::Named(RED));
+    }
+
+    #[test]
+    fn test_parse_named_color_synonyms_and_separators() {
+        assert_eq!("purple".parse::<Color>().unwrap(), Color::Named(PURPLE));
+        assert_eq!("magenta".parse::<Color>().unwrap(), Color::Named(PURPLE));
+        assert_eq!(
+            "bright-magenta".parse::<Color>().unwrap(),
+            Color::Named(BRIGHT_MAGENTA)
+        );
+        assert_eq!(
+            "bright_purple".parse::<Color>().unwrap(),
+            Color::Named(BRIGHT_MAGENTA)
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_named_color_errors() {
+        assert!("chartreuse".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_parse_indexed_color() {
+        assert_eq!("256:200".parse::<Color>().unwrap(), Color::Indexed(200));
+    }
+
+    #[test]
+    fn test_parse_indexed_color_out_of_range_errors() {
+        assert!("256:999".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_parse_truecolor_hex() {
+        assert_eq!(
+            "#ff8000".parse::<Color>().unwrap(),
+            Color::Rgb(0xff, 0x80, 0x00)
+        );
+    }
+
+    #[test]
+    fn test_parse_truecolor_wrong_length_errors() {
+        let err: Result<Color, _> = "#fff".parse();
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_parse_truecolor_non_hex_errors() {
+        let err: Result<Color, _> = "#zzzzzz".parse();
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_parse_color_error_display() {
+        let err = "not-a-color".parse::<Color>().unwrap_err();
+        assert_eq!(err.to_string(), "invalid color value: not-a-color");
+    }
+
+    #[test]
+    fn test_color_escape_for_each_variant() {
+        assert_eq!(Color::Named(BLUE).escape(), BLUE);
+        assert_eq!(Color::Indexed(200).escape(), "\x1b[38;5;200m");
+        assert_eq!(Color::Rgb(1, 2, 3).escape(), "\x1b[38;2;1;2;3m");
+    }
+}
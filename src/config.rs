@@ -0,0 +1,314 @@
+//! Prompt configuration resolved from CLI flags
+
+use crate::color::{Color, ShellType, BLUE, BRIGHT_BLACK, BRIGHT_MAGENTA, GREEN, PURPLE, RED};
+use std::borrow::Cow;
+
+/// Default JJ symbol prefix
+pub const DEFAULT_JJ_SYMBOL: &str = "\u{f1d6} ";
+/// Default Git symbol prefix
+pub const DEFAULT_GIT_SYMBOL: &str = "\u{e725} ";
+
+/// Default JJ prompt format, reproducing the original hardcoded layout
+pub const DEFAULT_JJ_FORMAT: &str = "(on $symbol)$change_id( $bookmarks)( $status)";
+/// Default Git prompt format, reproducing the original hardcoded layout
+pub const DEFAULT_GIT_FORMAT: &str = "(on $symbol)$branch( $commit)( $status)";
+
+/// Per-repo-type CLI flags for hiding individual prompt segments
+#[derive(Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DisplayFlags {
+    pub no_prefix: bool,
+    pub no_name: bool,
+    pub no_id: bool,
+    pub no_status: bool,
+    pub no_color: bool,
+    pub no_prefix_color: bool,
+}
+
+/// Resolved display toggles for a single repo type (jj or git)
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DisplayConfig {
+    pub show_prefix: bool,
+    pub show_name: bool,
+    pub show_id: bool,
+    pub show_status: bool,
+    pub show_color: bool,
+    pub show_prefix_color: bool,
+}
+
+impl DisplayConfig {
+    /// All segments visible and colored - used as a baseline and in tests
+    #[must_use]
+    pub fn all_visible() -> Self {
+        Self {
+            show_prefix: true,
+            show_name: true,
+            show_id: true,
+            show_status: true,
+            show_color: true,
+            show_prefix_color: true,
+        }
+    }
+
+    fn from_flags(flags: &DisplayFlags) -> Self {
+        Self {
+            show_prefix: !flags.no_prefix,
+            show_name: !flags.no_name,
+            show_id: !flags.no_id,
+            show_status: !flags.no_status,
+            show_color: !flags.no_color,
+            show_prefix_color: !flags.no_prefix_color,
+        }
+    }
+}
+
+/// Git-specific status rendering options that don't apply to jj
+#[derive(Debug, Clone, Default)]
+pub struct GitStatusConfig {
+    /// Collapse ahead+behind into a single `⇕` glyph instead of `⇡N⇣M`
+    pub collapse_diverged: bool,
+    /// When collapsing, still show the `⇡N⇣M` counts after `⇕`
+    pub diverged_show_counts: bool,
+    /// Symbol shown inside the status brackets (`[symbol]`) when synced with no changes
+    pub clean_symbol: Option<Cow<'static, str>>,
+}
+
+/// Color overrides for each `Palette` role, as raw strings from CLI flags -
+/// parsed the same way as the `JJ_STARSHIP_COLOR_*` environment variables
+#[derive(Debug, Default)]
+pub struct PaletteFlags {
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub id: Option<String>,
+    pub bookmarks: Option<String>,
+    pub status: Option<String>,
+    pub prefix_highlight: Option<String>,
+    pub prefix_rest: Option<String>,
+}
+
+/// Colors for each logical role a prompt segment can play, independent of
+/// the repo type (jj and git share one palette)
+#[derive(Debug, Clone)]
+pub struct Palette {
+    pub symbol: Color,
+    pub name: Color,
+    pub id: Color,
+    pub bookmarks: Color,
+    pub status: Color,
+    pub prefix_highlight: Color,
+    pub prefix_rest: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            symbol: Color::Named(BLUE),
+            name: Color::Named(PURPLE),
+            id: Color::Named(GREEN),
+            bookmarks: Color::Named(GREEN),
+            status: Color::Named(RED),
+            prefix_highlight: Color::Named(BRIGHT_MAGENTA),
+            prefix_rest: Color::Named(BRIGHT_BLACK),
+        }
+    }
+}
+
+impl Palette {
+    /// Resolve each role from, in priority order: an explicit CLI flag, the
+    /// role's `JJ_STARSHIP_COLOR_*` environment variable, then the default.
+    /// A value that fails to parse falls through to the next source.
+    #[must_use]
+    pub fn new(flags: PaletteFlags) -> Self {
+        let defaults = Self::default();
+        Self {
+            symbol: resolve_color(flags.symbol, "JJ_STARSHIP_COLOR_SYMBOL", defaults.symbol),
+            name: resolve_color(flags.name, "JJ_STARSHIP_COLOR_NAME", defaults.name),
+            id: resolve_color(flags.id, "JJ_STARSHIP_COLOR_ID", defaults.id),
+            bookmarks: resolve_color(
+                flags.bookmarks,
+                "JJ_STARSHIP_COLOR_BOOKMARKS",
+                defaults.bookmarks,
+            ),
+            status: resolve_color(flags.status, "JJ_STARSHIP_COLOR_STATUS", defaults.status),
+            prefix_highlight: resolve_color(
+                flags.prefix_highlight,
+                "JJ_STARSHIP_COLOR_PREFIX_HIGHLIGHT",
+                defaults.prefix_highlight,
+            ),
+            prefix_rest: resolve_color(
+                flags.prefix_rest,
+                "JJ_STARSHIP_COLOR_PREFIX_REST",
+                defaults.prefix_rest,
+            ),
+        }
+    }
+}
+
+fn resolve_color(cli_value: Option<String>, env_var: &str, default: Color) -> Color {
+    cli_value
+        .and_then(|value| value.parse().ok())
+        .or_else(|| {
+            std::env::var(env_var)
+                .ok()
+                .and_then(|value| value.parse().ok())
+        })
+        .unwrap_or(default)
+}
+
+/// Resolved prompt configuration
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub truncate_name: usize,
+    pub id_length: usize,
+    pub ancestor_bookmark_depth: usize,
+    pub jj_symbol: Cow<'static, str>,
+    pub git_symbol: Cow<'static, str>,
+    /// Format template for `output::format_jj` (see the `template` module)
+    pub jj_format: Cow<'static, str>,
+    /// Format template for `output::format_git` (see the `template` module)
+    pub git_format: Cow<'static, str>,
+    pub jj_display: DisplayConfig,
+    pub git_display: DisplayConfig,
+    pub git_status: GitStatusConfig,
+    pub palette: Palette,
+    /// Shell the prompt is rendered for, for zero-width color escaping
+    pub shell: ShellType,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            truncate_name: 0,
+            id_length: 8,
+            ancestor_bookmark_depth: 10,
+            jj_symbol: Cow::Borrowed(DEFAULT_JJ_SYMBOL),
+            git_symbol: Cow::Borrowed(DEFAULT_GIT_SYMBOL),
+            jj_format: Cow::Borrowed(DEFAULT_JJ_FORMAT),
+            git_format: Cow::Borrowed(DEFAULT_GIT_FORMAT),
+            jj_display: DisplayConfig::all_visible(),
+            git_display: DisplayConfig::all_visible(),
+            git_status: GitStatusConfig::default(),
+            palette: Palette::default(),
+            shell: ShellType::Other,
+        }
+    }
+}
+
+impl Config {
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(
+        truncate_name: Option<usize>,
+        id_length: Option<usize>,
+        ancestor_bookmark_depth: Option<usize>,
+        jj_symbol: Option<String>,
+        git_symbol: Option<String>,
+        no_symbol: bool,
+        jj_format: Option<String>,
+        git_format: Option<String>,
+        jj_flags: DisplayFlags,
+        git_flags: DisplayFlags,
+        git_status: GitStatusConfig,
+        palette_flags: PaletteFlags,
+        shell: ShellType,
+    ) -> Self {
+        let defaults = Self::default();
+        let (jj_symbol, git_symbol) = if no_symbol {
+            (Cow::Borrowed(""), Cow::Borrowed(""))
+        } else {
+            (
+                jj_symbol.map_or(defaults.jj_symbol.clone(), Cow::Owned),
+                git_symbol.map_or(defaults.git_symbol.clone(), Cow::Owned),
+            )
+        };
+
+        Self {
+            truncate_name: truncate_name.unwrap_or(defaults.truncate_name),
+            id_length: id_length.unwrap_or(defaults.id_length),
+            ancestor_bookmark_depth: ancestor_bookmark_depth
+                .unwrap_or(defaults.ancestor_bookmark_depth),
+            jj_symbol,
+            git_symbol,
+            jj_format: jj_format.map_or(defaults.jj_format, Cow::Owned),
+            git_format: git_format.map_or(defaults.git_format, Cow::Owned),
+            jj_display: DisplayConfig::from_flags(&jj_flags),
+            git_display: DisplayConfig::from_flags(&git_flags),
+            git_status,
+            palette: Palette::new(palette_flags),
+            shell,
+        }
+    }
+
+    /// Truncate `name` to `truncate_name` chars (0 = unlimited), appending `…`
+    /// when truncation happens so the original length stays visible
+    #[must_use]
+    pub fn truncate<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if self.truncate_name == 0 || name.chars().count() <= self.truncate_name {
+            Cow::Borrowed(name)
+        } else {
+            let kept: String = name
+                .chars()
+                .take(self.truncate_name.saturating_sub(1))
+                .collect();
+            Cow::Owned(format!("{kept}\u{2026}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::RED;
+
+    #[test]
+    fn test_resolve_color_prefers_cli_value() {
+        let resolved = resolve_color(
+            Some("red".to_string()),
+            "JJ_STARSHIP_TEST_COLOR_CLI",
+            Color::Named(BLUE),
+        );
+        assert_eq!(resolved, Color::Named(RED));
+    }
+
+    #[test]
+    fn test_resolve_color_falls_back_to_env_var() {
+        // SAFETY: test-only env var, not read concurrently by other tests.
+        unsafe { std::env::set_var("JJ_STARSHIP_TEST_COLOR_ENV", "red") };
+        let resolved = resolve_color(None, "JJ_STARSHIP_TEST_COLOR_ENV", Color::Named(BLUE));
+        unsafe { std::env::remove_var("JJ_STARSHIP_TEST_COLOR_ENV") };
+        assert_eq!(resolved, Color::Named(RED));
+    }
+
+    #[test]
+    fn test_resolve_color_falls_back_to_default_when_nothing_set() {
+        let resolved = resolve_color(None, "JJ_STARSHIP_TEST_COLOR_UNSET", Color::Named(BLUE));
+        assert_eq!(resolved, Color::Named(BLUE));
+    }
+
+    #[test]
+    fn test_invalid_cli_value_falls_through_to_valid_env_var() {
+        // A bogus CLI value must not shadow a valid env var override - each
+        // source is parsed independently rather than picking a string first.
+        unsafe { std::env::set_var("JJ_STARSHIP_TEST_COLOR_FALLTHROUGH", "red") };
+        let resolved = resolve_color(
+            Some("bogus".to_string()),
+            "JJ_STARSHIP_TEST_COLOR_FALLTHROUGH",
+            Color::Named(BLUE),
+        );
+        unsafe { std::env::remove_var("JJ_STARSHIP_TEST_COLOR_FALLTHROUGH") };
+        assert_eq!(resolved, Color::Named(RED));
+    }
+
+    #[test]
+    fn test_invalid_cli_and_env_values_fall_back_to_default() {
+        unsafe { std::env::set_var("JJ_STARSHIP_TEST_COLOR_BOTH_BOGUS", "also-bogus") };
+        let resolved = resolve_color(
+            Some("bogus".to_string()),
+            "JJ_STARSHIP_TEST_COLOR_BOTH_BOGUS",
+            Color::Named(BLUE),
+        );
+        unsafe { std::env::remove_var("JJ_STARSHIP_TEST_COLOR_BOTH_BOGUS") };
+        assert_eq!(resolved, Color::Named(BLUE));
+    }
+}
@@ -0,0 +1,26 @@
+//! Shared error type for repo collection backends
+
+use std::fmt;
+
+/// Error collecting repo info from a jj or git backend
+#[derive(Debug)]
+pub enum Error {
+    /// Error from the jj backend
+    Jj(String),
+    /// Error from a git backend (libgit2 or gix)
+    Git(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Jj(msg) => write!(f, "jj: {msg}"),
+            Error::Git(msg) => write!(f, "git: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Result alias for repo collection
+pub type Result<T> = std::result::Result<T, Error>;
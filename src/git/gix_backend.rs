@@ -0,0 +1,159 @@
+//! Pure-Rust `gix`-backed implementation of the `git::collect` contract
+
+use super::GitInfo;
+use crate::error::{Error, Result};
+use gix::bstr::ByteSlice;
+use std::path::Path;
+
+/// Collect Git repo info from the given path using gix
+pub fn collect(repo_root: &Path, id_length: usize) -> Result<GitInfo> {
+    let repo = gix::open(repo_root).map_err(|e| Error::Git(format!("open: {e}")))?;
+
+    let head = repo.head().map_err(|e| Error::Git(format!("head: {e}")))?;
+    let head_id = repo
+        .head_id()
+        .map_err(|e| Error::Git(format!("resolve head: {e}")))?;
+    let head_short = head_id
+        .to_hex()
+        .to_string()
+        .chars()
+        .take(id_length)
+        .collect();
+
+    // Detached HEAD has no referent branch name
+    let branch = head
+        .referent_name()
+        .and_then(|name| name.shorten().to_str().ok().map(String::from));
+
+    let mut staged = 0;
+    let mut modified = 0;
+    let mut untracked = 0;
+    let mut deleted = 0;
+    let mut renamed = 0;
+    let conflicted = conflicted_count(&repo).map_err(|e| Error::Git(format!("index: {e}")))?;
+
+    let status = repo
+        .status(gix::progress::Discard)
+        .map_err(|e| Error::Git(format!("status: {e}")))?
+        .into_iter(None)
+        .map_err(|e| Error::Git(format!("status iter: {e}")))?;
+
+    for item in status {
+        let item = item.map_err(|e| Error::Git(format!("status entry: {e}")))?;
+        use gix::status::index_worktree::iter::Item as WorktreeItem;
+        use gix::status::plumbing::index_as_worktree::Item as IndexItem;
+        match item {
+            gix::status::Item::IndexWorktree(WorktreeItem::DirectoryContents { .. }) => {
+                untracked += 1;
+            }
+            gix::status::Item::IndexWorktree(WorktreeItem::Modification { .. }) => {
+                modified += 1;
+            }
+            gix::status::Item::IndexWorktree(WorktreeItem::Rewrite { .. }) => {
+                renamed += 1;
+            }
+            gix::status::Item::TreeIndex(IndexItem::Removal { .. }) => {
+                deleted += 1;
+            }
+            // Any other staged change, a staged rename included, counts as staged
+            gix::status::Item::TreeIndex(_) => {
+                staged += 1;
+            }
+        }
+    }
+
+    let (ahead, behind) = branch
+        .as_deref()
+        .and_then(|name| ahead_behind(&repo, name).ok())
+        .unwrap_or((0, 0));
+
+    let stashed = stash_count(&repo);
+
+    Ok(GitInfo {
+        branch,
+        head_short,
+        staged,
+        modified,
+        untracked,
+        deleted,
+        conflicted,
+        renamed,
+        ahead,
+        behind,
+        stashed,
+    })
+}
+
+/// Count paths with an unresolved merge conflict: the index carries them as
+/// multiple non-zero "stage" entries (base/ours/theirs) for the same path
+/// instead of a single unconflicted one, so dedupe by path before counting.
+fn conflicted_count(repo: &gix::Repository) -> Result<usize> {
+    let index = repo
+        .index_or_empty()
+        .map_err(|e| Error::Git(format!("read index: {e}")))?;
+    let paths: std::collections::HashSet<_> = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.stage() != gix::index::entry::Stage::Unconflicted)
+        .map(|entry| entry.path(&index))
+        .collect();
+    Ok(paths.len())
+}
+
+/// Count entries in the stash reflog (`refs/stash`); a repo with no stashes
+/// simply has no such reference
+fn stash_count(repo: &gix::Repository) -> usize {
+    let Ok(Some(mut stash_ref)) = repo.try_find_reference("refs/stash") else {
+        return 0;
+    };
+    stash_ref
+        .log_iter()
+        .all()
+        .ok()
+        .flatten()
+        .map(Iterator::count)
+        .unwrap_or(0)
+}
+
+/// Count commits ahead/behind the branch's upstream via ancestor traversal and merge-base
+fn ahead_behind(repo: &gix::Repository, branch_name: &str) -> Result<(usize, usize)> {
+    let local_ref = repo
+        .find_reference(&format!("refs/heads/{branch_name}"))
+        .map_err(|e| Error::Git(format!("find branch: {e}")))?;
+    let upstream_ref = repo
+        .branch_remote_tracking_ref_name(local_ref.name(), gix::remote::Direction::Fetch)
+        .ok_or_else(|| Error::Git("no upstream configured".into()))?
+        .map_err(|e| Error::Git(format!("upstream ref name: {e}")))?;
+
+    let local_id = local_ref
+        .clone()
+        .into_fully_peeled_id()
+        .map_err(|e| Error::Git(format!("peel local: {e}")))?
+        .detach();
+    let upstream_id = repo
+        .find_reference(upstream_ref.as_ref())
+        .map_err(|e| Error::Git(format!("find upstream: {e}")))?
+        .into_fully_peeled_id()
+        .map_err(|e| Error::Git(format!("peel upstream: {e}")))?
+        .detach();
+
+    let merge_base = repo
+        .merge_base(local_id, upstream_id)
+        .map_err(|e| Error::Git(format!("merge base: {e}")))?
+        .detach();
+
+    let ahead = repo
+        .rev_walk([local_id])
+        .with_boundary([merge_base])
+        .all()
+        .map_err(|e| Error::Git(format!("walk ahead: {e}")))?
+        .count();
+    let behind = repo
+        .rev_walk([upstream_id])
+        .with_boundary([merge_base])
+        .all()
+        .map_err(|e| Error::Git(format!("walk behind: {e}")))?
+        .count();
+
+    Ok((ahead, behind))
+}
@@ -0,0 +1,113 @@
+//! libgit2-backed implementation of the `git::collect` contract
+
+use super::GitInfo;
+use crate::error::{Error, Result};
+use git2::{Repository, StatusOptions};
+use std::path::Path;
+
+/// Collect Git repo info from the given path using libgit2
+pub fn collect(repo_root: &Path, id_length: usize) -> Result<GitInfo> {
+    let mut repo = Repository::open(repo_root).map_err(|e| Error::Git(format!("open: {e}")))?;
+
+    let head = repo.head().map_err(|e| Error::Git(format!("head: {e}")))?;
+    let head_commit = head
+        .peel_to_commit()
+        .map_err(|e| Error::Git(format!("peel head: {e}")))?;
+    let head_short = head_commit
+        .id()
+        .to_string()
+        .chars()
+        .take(id_length)
+        .collect();
+
+    // Detached HEAD: `head.shorthand()` returns "HEAD" rather than a branch name
+    let branch = head
+        .is_branch()
+        .then(|| head.shorthand().map(String::from))
+        .flatten();
+
+    let mut staged = 0;
+    let mut modified = 0;
+    let mut untracked = 0;
+    let mut deleted = 0;
+    let mut conflicted = 0;
+    let mut renamed = 0;
+
+    let mut status_opts = StatusOptions::new();
+    status_opts.include_untracked(true);
+    let statuses = repo
+        .statuses(Some(&mut status_opts))
+        .map_err(|e| Error::Git(format!("status: {e}")))?;
+
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.is_conflicted() {
+            conflicted += 1;
+            continue;
+        }
+        if status.is_index_renamed() || status.is_wt_renamed() {
+            renamed += 1;
+            continue;
+        }
+        if status.is_index_new() || status.is_index_modified() {
+            staged += 1;
+        }
+        if status.is_wt_modified() {
+            modified += 1;
+        }
+        if status.is_wt_new() {
+            untracked += 1;
+        }
+        if status.is_wt_deleted() || status.is_index_deleted() {
+            deleted += 1;
+        }
+    }
+
+    let (ahead, behind) = branch
+        .as_deref()
+        .and_then(|name| ahead_behind(&repo, name).ok())
+        .unwrap_or((0, 0));
+
+    let mut stashed = 0;
+    repo.stash_foreach(|_, _, _| {
+        stashed += 1;
+        true
+    })
+    .map_err(|e| Error::Git(format!("stash: {e}")))?;
+
+    Ok(GitInfo {
+        branch,
+        head_short,
+        staged,
+        modified,
+        untracked,
+        deleted,
+        conflicted,
+        renamed,
+        ahead,
+        behind,
+        stashed,
+    })
+}
+
+/// Count commits ahead/behind the branch's upstream, if one is configured
+fn ahead_behind(repo: &Repository, branch_name: &str) -> Result<(usize, usize)> {
+    let local_branch = repo
+        .find_branch(branch_name, git2::BranchType::Local)
+        .map_err(|e| Error::Git(format!("find branch: {e}")))?;
+    let upstream = local_branch
+        .upstream()
+        .map_err(|e| Error::Git(format!("upstream: {e}")))?;
+
+    let local_oid = local_branch
+        .get()
+        .target()
+        .ok_or_else(|| Error::Git("local branch has no target".into()))?;
+    let upstream_oid = upstream
+        .get()
+        .target()
+        .ok_or_else(|| Error::Git("upstream branch has no target".into()))?;
+
+    repo.graph_ahead_behind(local_oid, upstream_oid)
+        .map_err(|e| Error::Git(format!("ahead/behind: {e}")))
+}
@@ -0,0 +1,60 @@
+//! Git repository info collection
+//!
+//! Two interchangeable backends implement the same contract below: `libgit2`
+//! (the default, via the `git` feature) and `gix` (pure Rust, via the `gix`
+//! feature). Pick whichever links more cleanly on your platform - only the
+//! backend differs, `GitInfo` and `collect` stay the same either way.
+
+#[cfg(feature = "git")]
+mod libgit2;
+
+#[cfg(feature = "gix")]
+mod gix_backend;
+
+use crate::error::Result;
+use std::path::Path;
+
+/// Git repository status info
+#[derive(Debug, serde::Serialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct GitInfo {
+    /// Current branch name, or `None` when HEAD is detached
+    pub branch: Option<String>,
+    /// Short commit id at HEAD
+    pub head_short: String,
+    /// Staged file count
+    pub staged: usize,
+    /// Modified (unstaged) file count
+    pub modified: usize,
+    /// Untracked file count
+    pub untracked: usize,
+    /// Deleted file count
+    pub deleted: usize,
+    /// Conflicted (unmerged) file count
+    pub conflicted: usize,
+    /// Renamed file count: staged or worktree renames with libgit2; worktree
+    /// renames only with gix, which counts a staged rename toward `staged`
+    pub renamed: usize,
+    /// Commits ahead of the upstream branch
+    pub ahead: usize,
+    /// Commits behind the upstream branch
+    pub behind: usize,
+    /// Number of stash entries in the repo
+    pub stashed: usize,
+}
+
+/// Collect Git repo info from the given path.
+///
+/// Dispatches to whichever backend is enabled. When both `git` and `gix` are
+/// enabled, `gix` wins, since it's the one without the libgit2 link dependency.
+#[must_use = "returns collected repo info, does not modify state"]
+pub fn collect(repo_root: &Path, id_length: usize) -> Result<GitInfo> {
+    #[cfg(feature = "gix")]
+    {
+        gix_backend::collect(repo_root, id_length)
+    }
+    #[cfg(all(feature = "git", not(feature = "gix")))]
+    {
+        libgit2::collect(repo_root, id_length)
+    }
+}
@@ -6,9 +6,12 @@ use jj_lib::hex_util::encode_reverse_hex;
 use jj_lib::object_id::ObjectId;
 use jj_lib::ref_name::RefName;
 use jj_lib::repo::{Repo, StoreFactories};
+use jj_lib::revset::{
+    optimize, parse, RevsetAliasesMap, RevsetExtensions, RevsetParseContext, RevsetWorkspaceContext,
+};
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::{StringMatcher, StringPattern};
-use jj_lib::workspace::{Workspace, default_working_copy_factories};
+use jj_lib::workspace::{default_working_copy_factories, Workspace};
 use std::path::Path;
 use std::sync::Arc;
 
@@ -16,7 +19,7 @@ use std::sync::Arc;
 ///
 /// Bool fields are independent, orthogonal status flags - each can be
 /// true/false independently. Bitflags would add complexity without benefit.
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct JjInfo {
     /// Short change ID (8 chars)
@@ -36,13 +39,40 @@ pub struct JjInfo {
     pub has_remote: bool,
     /// Whether any bookmark is synced with remote
     pub is_synced: bool,
+    /// Names of bookmarks (local or tracked-remote) whose `RefTarget` is
+    /// conflicted, e.g. after a concurrent import left it pointing at multiple commits
+    pub conflicted_bookmarks: Vec<String>,
+    /// The op log has more than one head (concurrent operations not yet reconciled)
+    pub divergent_operations: bool,
+    /// Commits in `remote_target..local_target` for the closest bookmark's remote
+    pub ahead: usize,
+    /// Commits in `local_target..remote_target` for the closest bookmark's remote
+    pub behind: usize,
+    /// Whether `ahead` hit the `ancestor_bookmark_depth` cap (count is a lower bound)
+    pub ahead_capped: bool,
+    /// Whether `behind` hit the `ancestor_bookmark_depth` cap (count is a lower bound)
+    pub behind_capped: bool,
 }
 
-/// Create minimal `UserSettings` for read-only operations
+/// Create `UserSettings` for read-only operations, layering in the user's real
+/// jj config (so the `revset-aliases."immutable_heads()"` override is visible
+/// to [`configured_immutable_heads`]) on top of the minimal defaults we require.
+/// The change id prefix length does not use `revsets.short-prefixes`.
 fn create_user_settings() -> Result<UserSettings> {
     let mut config = StackedConfig::with_defaults();
 
-    // Minimal config required by UserSettings
+    // Best-effort: load the user's actual config file(s) so aliases like
+    // `immutable_heads()` are visible. Silently skip on any failure - this is
+    // a read-only prompt helper, not `jj`'s own config loader, and a missing
+    // or unreadable config must not block prompt generation.
+    if let Ok(config_path) = jj_lib::config::config_dir().map(|dir| dir.join("config.toml")) {
+        if let Ok(layer) = ConfigLayer::load_from_file(ConfigSource::User, config_path) {
+            config.add_layer(layer);
+        }
+    }
+
+    // Minimal config required by UserSettings, applied last so it always wins
+    // over anything (unexpectedly) missing from the user's own config.
     let mut user_layer = ConfigLayer::empty(ConfigSource::User);
     user_layer
         .set_value("user.name", "jj-starship")
@@ -55,9 +85,58 @@ fn create_user_settings() -> Result<UserSettings> {
     UserSettings::from_config(config).map_err(|e| Error::Jj(format!("settings: {e}")))
 }
 
+/// Read the user's `revset-aliases."immutable_heads()"` override, if any, and
+/// evaluate it against the loaded repo. Mirrors jj's own resolution of
+/// `immutable_heads()` (default: `present(trunk()) | tags() | untracked_remote_bookmarks()`).
+/// Only the `immutable_heads()` alias itself is loaded: an expression that calls
+/// another alias, such as `trunk()`, fails to parse.
+/// Returns `None` when no alias is configured or parsing/evaluation fails, in which
+/// case the caller should fall back to the hardcoded heuristic.
+fn configured_immutable_heads(
+    repo: &Arc<jj_lib::repo::ReadonlyRepo>,
+    workspace: &Workspace,
+    settings: &UserSettings,
+) -> Option<std::collections::HashSet<jj_lib::backend::CommitId>> {
+    let config = settings.config();
+    let alias_value = config
+        .get_value("revset-aliases", "immutable_heads()")
+        .ok()?;
+    let expression = alias_value.as_str()?;
+
+    let mut aliases_map = RevsetAliasesMap::new();
+    aliases_map
+        .insert("immutable_heads()", expression.to_string())
+        .ok()?;
+
+    let workspace_ctx = RevsetWorkspaceContext {
+        path_converter: workspace.path_converter(),
+        workspace_name: workspace.workspace_name(),
+    };
+    let extensions = RevsetExtensions::default();
+    let parse_ctx = RevsetParseContext::new(
+        &aliases_map,
+        settings.clone(),
+        extensions.symbol_resolvers(),
+        Some(workspace_ctx),
+    );
+
+    let parsed = parse("immutable_heads()", &parse_ctx).ok()?;
+    let optimized = optimize(parsed);
+    let resolved = optimized
+        .resolve_user_expression(repo.as_ref(), &extensions.symbol_resolvers())
+        .ok()?;
+    let revset = resolved.evaluate(repo.as_ref()).ok()?;
+
+    let ids: std::collections::HashSet<jj_lib::backend::CommitId> =
+        revset.iter().filter_map(std::result::Result::ok).collect();
+    Some(ids)
+}
+
 /// Find immutable head commits (trunk + tags + untracked remote bookmarks)
-/// Mirrors jj's `builtin_immutable_heads()` without revset evaluation
-fn find_immutable_heads(
+/// Mirrors jj's `builtin_immutable_heads()` without revset evaluation.
+/// This is the fallback used when no `immutable_heads()` alias is configured,
+/// or when parsing/evaluating that alias fails.
+fn default_immutable_heads(
     view: &jj_lib::view::View,
 ) -> std::collections::HashSet<jj_lib::backend::CommitId> {
     use std::collections::HashSet;
@@ -103,6 +182,8 @@ fn find_immutable_heads(
 /// Returns bookmarks sorted by distance (closest first)
 fn find_ancestor_bookmarks(
     repo: &Arc<jj_lib::repo::ReadonlyRepo>,
+    workspace: &Workspace,
+    settings: &UserSettings,
     view: &jj_lib::view::View,
     wc_id: &jj_lib::backend::CommitId,
     max_depth: usize,
@@ -113,8 +194,11 @@ fn find_ancestor_bookmarks(
     let mut visited = HashSet::new();
     let mut bookmarks_with_distances: HashMap<String, usize> = HashMap::new();
 
-    // Pre-compute immutable heads to stop traversal at trunk/tags/untracked remotes
-    let immutable_heads = find_immutable_heads(view);
+    // Pre-compute immutable heads to stop traversal at trunk/tags/untracked remotes.
+    // Prefer the user's configured `immutable_heads()` revset; fall back to the
+    // hardcoded heuristic when none is configured or it fails to resolve.
+    let immutable_heads = configured_immutable_heads(repo, workspace, settings)
+        .unwrap_or_else(|| default_immutable_heads(view));
 
     // Start BFS from WC commit parents
     let wc_commit = repo
@@ -168,6 +252,151 @@ fn find_ancestor_bookmarks(
     Ok(result)
 }
 
+/// Result of [`ahead_behind`]: commit counts plus whether either side hit the
+/// traversal depth cap (in which case the count is a lower bound).
+struct AheadBehind {
+    ahead: usize,
+    behind: usize,
+    ahead_capped: bool,
+    behind_capped: bool,
+}
+
+/// Breadth-first ancestor depths of `start`, up to `max_depth` levels.
+/// `start` itself is at depth 0. A commit reachable by more than one path
+/// keeps its shortest (first-seen) depth, since BFS visits in depth order.
+/// Returns whether the frontier was still non-empty at the depth cap (the
+/// map may be missing ancestors beyond it).
+fn ancestor_depths(
+    repo: &Arc<jj_lib::repo::ReadonlyRepo>,
+    start: &jj_lib::backend::CommitId,
+    max_depth: usize,
+) -> Result<(
+    std::collections::HashMap<jj_lib::backend::CommitId, usize>,
+    bool,
+)> {
+    use std::collections::{HashMap, VecDeque};
+
+    let mut depths = HashMap::new();
+    depths.insert(start.clone(), 0);
+    let mut frontier: VecDeque<jj_lib::backend::CommitId> = VecDeque::from([start.clone()]);
+
+    for depth in 1..=max_depth {
+        if frontier.is_empty() {
+            break;
+        }
+        let mut next = VecDeque::new();
+        while let Some(id) = frontier.pop_front() {
+            let commit = repo
+                .store()
+                .get_commit(&id)
+                .map_err(|e| Error::Jj(format!("get commit: {e}")))?;
+            for parent_id in commit.parent_ids() {
+                if depths.contains_key(parent_id) {
+                    continue;
+                }
+                depths.insert(parent_id.clone(), depth);
+                next.push_back(parent_id.clone());
+            }
+        }
+        frontier = next;
+    }
+
+    let capped = !frontier.is_empty();
+    Ok((depths, capped))
+}
+
+/// Given each side's ancestor-depth map, find their nearest common ancestor
+/// (the one minimizing the sum of both depths) and count the commits unique
+/// to each side as those strictly shallower than the merge base on that side.
+/// If no common ancestor was found (the depth maps never intersected), the
+/// full explored sets are treated as unique; a side's count is a lower bound
+/// only if that side hit the depth cap.
+fn merge_base_counts<K: Eq + std::hash::Hash>(
+    local_depths: &std::collections::HashMap<K, usize>,
+    remote_depths: &std::collections::HashMap<K, usize>,
+    local_capped: bool,
+    remote_capped: bool,
+) -> AheadBehind {
+    let merge_base = local_depths
+        .iter()
+        .filter_map(|(id, &local_depth)| {
+            remote_depths
+                .get(id)
+                .map(|&remote_depth| (local_depth, remote_depth))
+        })
+        .min_by_key(|&(local_depth, remote_depth)| local_depth + remote_depth);
+
+    let Some((local_mb_depth, remote_mb_depth)) = merge_base else {
+        // No common ancestor within the explored depth on either side: if a
+        // side's frontier was still non-empty at the cap, its count is a
+        // lower bound; if it was fully exhausted, the histories are simply
+        // unrelated and the count is exact.
+        return AheadBehind {
+            ahead: local_depths.len(),
+            behind: remote_depths.len(),
+            ahead_capped: local_capped,
+            behind_capped: remote_capped,
+        };
+    };
+
+    // A merge base was found, so both counts are exact - even if a depth map
+    // hit the cap elsewhere, that's history beyond the merge base we never
+    // needed to look at.
+    let ahead = local_depths
+        .values()
+        .filter(|&&d| d < local_mb_depth)
+        .count();
+    let behind = remote_depths
+        .values()
+        .filter(|&&d| d < remote_mb_depth)
+        .count();
+
+    AheadBehind {
+        ahead,
+        behind,
+        ahead_capped: false,
+        behind_capped: false,
+    }
+}
+
+/// Count commits unique to each side of `local_id`/`remote_id`: walk both
+/// sides' ancestors independently up to `max_depth`, then find their nearest
+/// common ancestor (merge base) and count commits strictly more recent than
+/// it on each side. Bounded by `max_depth` so a long-diverged history doesn't
+/// walk the whole repo.
+fn ahead_behind(
+    repo: &Arc<jj_lib::repo::ReadonlyRepo>,
+    local_id: &jj_lib::backend::CommitId,
+    remote_id: &jj_lib::backend::CommitId,
+    max_depth: usize,
+) -> Result<AheadBehind> {
+    if local_id == remote_id {
+        return Ok(AheadBehind {
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
+        });
+    }
+
+    let (local_depths, local_capped) = ancestor_depths(repo, local_id, max_depth)?;
+    let (remote_depths, remote_capped) = ancestor_depths(repo, remote_id, max_depth)?;
+
+    Ok(merge_base_counts(
+        &local_depths,
+        &remote_depths,
+        local_capped,
+        remote_capped,
+    ))
+}
+
+/// Whether a `RefTarget` is in a conflicted state: present (not absent) but not
+/// resolvable to a single commit. `as_normal()` returns `None` for both the
+/// absent and the conflicted case, so those must be told apart explicitly.
+fn ref_target_conflicted(target: &jj_lib::op_store::RefTarget) -> bool {
+    !target.is_absent() && target.as_normal().is_none()
+}
+
 /// Collect JJ repo info from the given path
 #[must_use = "returns collected repo info, does not modify state"]
 pub fn collect(repo_root: &Path, id_length: usize, ancestor_depth: usize) -> Result<JjInfo> {
@@ -181,6 +410,16 @@ pub fn collect(repo_root: &Path, id_length: usize, ancestor_depth: usize) -> Res
     )
     .map_err(|e| Error::Jj(format!("load workspace: {e}")))?;
 
+    // Read the op-heads count before `load_at_head()` resolves/merges them -
+    // that resolution is exactly what collapses a divergent op log back down
+    // to one head as a side effect, so this must run first or there would
+    // almost never be more than one head left to see.
+    let divergent_operations = workspace
+        .repo_loader()
+        .op_heads_store()
+        .get_op_heads()
+        .is_ok_and(|heads| heads.len() > 1);
+
     let repo: Arc<jj_lib::repo::ReadonlyRepo> = workspace
         .repo_loader()
         .load_at_head()
@@ -233,14 +472,35 @@ pub fn collect(repo_root: &Path, id_length: usize, ancestor_depth: usize) -> Res
     // Always search ancestors if enabled (useful for stacked PR context)
     // Ancestor bookmarks are disjoint from direct bookmarks (different commits)
     if ancestor_depth > 0 {
-        let ancestors = find_ancestor_bookmarks(&repo, view, wc_id, ancestor_depth)?;
+        let ancestors =
+            find_ancestor_bookmarks(&repo, &workspace, &settings, view, wc_id, ancestor_depth)?;
         bookmarks.extend(ancestors);
     }
 
+    // Surface bookmarks whose local or tracked-remote target is conflicted
+    // (e.g. after a concurrent `jj` import) so the prompt can warn about them.
+    let mut conflicted_bookmarks: Vec<String> = Vec::new();
+    for (bm_name, _) in &bookmarks {
+        let local_target = view.get_local_bookmark(RefName::new(bm_name));
+        let local_conflicted = ref_target_conflicted(local_target);
+
+        let name_matcher = StringPattern::exact(bm_name).to_matcher();
+        let remote_conflicted = view
+            .remote_bookmarks_matching(&name_matcher, &StringMatcher::All)
+            .filter(|(symbol, _)| symbol.remote.as_str() != "git")
+            .any(|(_, remote_ref)| ref_target_conflicted(&remote_ref.target));
+
+        if local_conflicted || remote_conflicted {
+            conflicted_bookmarks.push(bm_name.clone());
+        }
+    }
+
     // Check remote sync status for first (closest) bookmark only
     // For stacked PRs, this reflects whether current stack position needs pushing
-    let (has_remote, is_synced) = if bookmarks.is_empty() {
-        (false, true)
+    let (has_remote, is_synced, ahead, behind, ahead_capped, behind_capped) = if bookmarks
+        .is_empty()
+    {
+        (false, true, 0, 0, false, false)
     } else {
         let (bm_name, _) = &bookmarks[0];
         let local_target = view.get_local_bookmark(RefName::new(bm_name));
@@ -248,6 +508,7 @@ pub fn collect(repo_root: &Path, id_length: usize, ancestor_depth: usize) -> Res
         let name_matcher = StringPattern::exact(bm_name).to_matcher();
         let mut has_remote = false;
         let mut is_synced = false;
+        let mut ahead_behind_counts = None;
 
         for (symbol, remote_ref) in
             view.remote_bookmarks_matching(&name_matcher, &StringMatcher::All)
@@ -258,11 +519,41 @@ pub fn collect(repo_root: &Path, id_length: usize, ancestor_depth: usize) -> Res
             has_remote = true;
             if remote_ref.target == *local_target {
                 is_synced = true;
+                ahead_behind_counts = Some(AheadBehind {
+                    ahead: 0,
+                    behind: 0,
+                    ahead_capped: false,
+                    behind_capped: false,
+                });
                 break;
             }
+            if let (Some(local_id), Some(remote_id)) =
+                (local_target.as_normal(), remote_ref.target.as_normal())
+            {
+                ahead_behind_counts = ahead_behind(&repo, local_id, remote_id, ancestor_depth).ok();
+            }
         }
 
-        (has_remote, is_synced || !has_remote)
+        let AheadBehind {
+            ahead,
+            behind,
+            ahead_capped,
+            behind_capped,
+        } = ahead_behind_counts.unwrap_or(AheadBehind {
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
+        });
+
+        (
+            has_remote,
+            is_synced || !has_remote,
+            ahead,
+            behind,
+            ahead_capped,
+            behind_capped,
+        )
     };
 
     Ok(JjInfo {
@@ -274,5 +565,90 @@ pub fn collect(repo_root: &Path, id_length: usize, ancestor_depth: usize) -> Res
         divergent,
         has_remote,
         is_synced,
+        conflicted_bookmarks,
+        divergent_operations,
+        ahead,
+        behind,
+        ahead_capped,
+        behind_capped,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jj_lib::testutils::{self, TestWorkspace};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_divergent_operations_detected_before_reconciliation() {
+        let settings = testutils::user_settings();
+        let mut test_workspace = TestWorkspace::init(&settings);
+        let repo = test_workspace.repo.clone();
+
+        // Two transactions started from the same parent operation, each
+        // unaware of the other - this is how two concurrent `jj` invocations
+        // (or two machines sharing a colocated repo) leave the op log with
+        // more than one head until something reconciles them.
+        let mut tx_a = repo.start_transaction(&settings);
+        tx_a.commit("first concurrent op");
+        let mut tx_b = repo.start_transaction(&settings);
+        tx_b.commit("second concurrent op");
+
+        let info = collect(test_workspace.workspace.workspace_root(), 8, 10)
+            .expect("collect should succeed against the fixture workspace");
+        assert!(info.divergent_operations);
+    }
+
+    #[test]
+    fn test_remote_is_direct_ancestor_of_local() {
+        // local -> remote -> ...: one unpushed commit, nothing to pull
+        let local_depths = HashMap::from([("local", 0), ("remote", 1), ("older", 2)]);
+        let remote_depths = HashMap::from([("remote", 0), ("older", 1)]);
+        let result = merge_base_counts(&local_depths, &remote_depths, false, false);
+        assert_eq!(result.ahead, 1);
+        assert_eq!(result.behind, 0);
+        assert!(!result.ahead_capped);
+        assert!(!result.behind_capped);
+    }
+
+    #[test]
+    fn test_local_is_direct_ancestor_of_remote() {
+        // remote -> local -> ...: nothing to push, one commit to pull
+        let local_depths = HashMap::from([("local", 0), ("older", 1)]);
+        let remote_depths = HashMap::from([("remote", 0), ("local", 1), ("older", 2)]);
+        let result = merge_base_counts(&local_depths, &remote_depths, false, false);
+        assert_eq!(result.ahead, 0);
+        assert_eq!(result.behind, 1);
+    }
+
+    #[test]
+    fn test_true_divergence() {
+        // local: local -> a -> base; remote: remote -> b -> c -> base
+        let local_depths = HashMap::from([("local", 0), ("a", 1), ("base", 2)]);
+        let remote_depths = HashMap::from([("remote", 0), ("b", 1), ("c", 2), ("base", 3)]);
+        let result = merge_base_counts(&local_depths, &remote_depths, false, false);
+        assert_eq!(result.ahead, 2);
+        assert_eq!(result.behind, 3);
+    }
+
+    #[test]
+    fn test_identical_history_has_nothing_unique() {
+        let local_depths = HashMap::from([("base", 0)]);
+        let remote_depths = HashMap::from([("base", 0)]);
+        let result = merge_base_counts(&local_depths, &remote_depths, false, false);
+        assert_eq!(result.ahead, 0);
+        assert_eq!(result.behind, 0);
+    }
+
+    #[test]
+    fn test_no_common_ancestor_within_depth_is_capped() {
+        let local_depths = HashMap::from([("local", 0), ("a", 1)]);
+        let remote_depths = HashMap::from([("remote", 0), ("b", 1)]);
+        let result = merge_base_counts(&local_depths, &remote_depths, true, true);
+        assert_eq!(result.ahead, 2);
+        assert_eq!(result.behind, 2);
+        assert!(result.ahead_capped);
+        assert!(result.behind_capped);
+    }
+}
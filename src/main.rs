@@ -4,16 +4,20 @@ mod color;
 mod config;
 mod detect;
 mod error;
-#[cfg(feature = "git")]
+#[cfg(any(feature = "git", feature = "gix"))]
 mod git;
 mod jj;
 mod output;
+mod template;
 
-#[cfg(feature = "git")]
+#[cfg(any(feature = "git", feature = "gix"))]
 use clap::Args;
-use clap::{Parser, Subcommand};
-use config::{Config, DisplayFlags};
+use clap::{Parser, Subcommand, ValueEnum};
+use color::ShellType;
+use config::{Config, DisplayFlags, GitStatusConfig, PaletteFlags};
 use detect::RepoType;
+#[cfg(any(feature = "git", feature = "gix"))]
+use std::borrow::Cow;
 use std::env;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
@@ -27,6 +31,10 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
 
+    /// Output format for the prompt command
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
     /// Override working directory
     #[arg(long, global = true)]
     cwd: Option<PathBuf>,
@@ -51,10 +59,18 @@ struct Cli {
     #[arg(long, global = true)]
     no_symbol: bool,
 
+    /// Prompt format template for JJ repos, e.g. `$change_id( $bookmarks)`
+    #[arg(long, global = true)]
+    jj_format: Option<String>,
+
     /// Disable output styling
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// Shell to emit zero-width color escapes for (default: detected from $SHELL)
+    #[arg(long, global = true)]
+    shell: Option<String>,
+
     // JJ display flags
     /// Hide "on {symbol}" prefix for JJ repos
     #[arg(long, global = true)]
@@ -72,18 +88,44 @@ struct Cli {
     #[arg(long, global = true)]
     no_prefix_color: bool,
 
-    #[cfg(feature = "git")]
+    // Palette overrides
+    /// Color for the "on {symbol}" prefix (e.g. "blue", "256:33", "#61afef")
+    #[arg(long, global = true)]
+    color_symbol: Option<String>,
+    /// Color for the Git branch name (and the JJ `change_id` when prefix coloring is off)
+    #[arg(long, global = true)]
+    color_name: Option<String>,
+    /// Color for the Git commit id
+    #[arg(long, global = true)]
+    color_id: Option<String>,
+    /// Color for the bookmarks segment
+    #[arg(long, global = true)]
+    color_bookmarks: Option<String>,
+    /// Color for the status segment
+    #[arg(long, global = true)]
+    color_status: Option<String>,
+    /// Color for the unique `change_id` prefix highlight
+    #[arg(long, global = true)]
+    color_prefix_highlight: Option<String>,
+    /// Color for the non-unique `change_id` rest
+    #[arg(long, global = true)]
+    color_prefix_rest: Option<String>,
+
+    #[cfg(any(feature = "git", feature = "gix"))]
     #[command(flatten)]
     git: GitArgs,
 }
 
-#[cfg(feature = "git")]
+#[cfg(any(feature = "git", feature = "gix"))]
 #[derive(Args)]
 #[allow(clippy::struct_excessive_bools)]
 struct GitArgs {
     /// Symbol prefix for Git repos (default: "")
     #[arg(long, global = true)]
     git_symbol: Option<String>,
+    /// Prompt format template for Git repos, e.g. `$branch( $commit)`
+    #[arg(long, global = true)]
+    git_format: Option<String>,
     /// Hide "on {symbol}" prefix for Git repos
     #[arg(long, global = true)]
     no_git_prefix: bool,
@@ -96,6 +138,25 @@ struct GitArgs {
     /// Hide [status] for Git repos
     #[arg(long, global = true)]
     no_git_status: bool,
+    /// Collapse ahead+behind into a single `⇕` instead of `⇡N⇣M`
+    #[arg(long, global = true)]
+    git_collapse_diverged: bool,
+    /// With `--git-collapse-diverged`, keep showing `⇡N⇣M` counts after `⇕`
+    #[arg(long, global = true)]
+    git_diverged_counts: bool,
+    /// Symbol shown in [status] when the repo is synced with no changes
+    #[arg(long, global = true)]
+    git_clean_symbol: Option<String>,
+}
+
+/// Output format for `Command::Prompt`
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum OutputFormat {
+    /// Styled prompt string (default)
+    #[default]
+    Text,
+    /// Stable JSON object exposing the collected repo info for downstream tools
+    Json,
 }
 
 #[derive(Subcommand)]
@@ -123,9 +184,10 @@ fn main() -> ExitCode {
         no_prefix_color: cli.no_prefix_color,
     };
 
-    #[cfg(feature = "git")]
-    let (git_symbol, git_flags) = (
+    #[cfg(any(feature = "git", feature = "gix"))]
+    let (git_symbol, git_format, git_flags, git_status) = (
         cli.git.git_symbol,
+        cli.git.git_format,
         DisplayFlags {
             no_prefix: cli.git.no_git_prefix,
             no_name: cli.git.no_git_name,
@@ -134,9 +196,35 @@ fn main() -> ExitCode {
             no_color: cli.no_color,
             no_prefix_color: false, // N/A for git
         },
+        GitStatusConfig {
+            collapse_diverged: cli.git.git_collapse_diverged,
+            diverged_show_counts: cli.git.git_diverged_counts,
+            clean_symbol: cli.git.git_clean_symbol.map(Cow::Owned),
+        },
     );
-    #[cfg(not(feature = "git"))]
-    let (git_symbol, git_flags): (Option<String>, DisplayFlags) = (None, DisplayFlags::default());
+    #[cfg(not(any(feature = "git", feature = "gix")))]
+    let (git_symbol, git_format, git_flags, git_status): (
+        Option<String>,
+        Option<String>,
+        DisplayFlags,
+        GitStatusConfig,
+    ) = (None, None, DisplayFlags::default(), GitStatusConfig::default());
+
+    let shell = cli
+        .shell
+        .or_else(|| env::var("SHELL").ok())
+        .map(|s| ShellType::detect(Some(&s)))
+        .unwrap_or_default();
+
+    let palette_flags = PaletteFlags {
+        symbol: cli.color_symbol,
+        name: cli.color_name,
+        id: cli.color_id,
+        bookmarks: cli.color_bookmarks,
+        status: cli.color_status,
+        prefix_highlight: cli.color_prefix_highlight,
+        prefix_rest: cli.color_prefix_rest,
+    };
 
     let config = Config::new(
         cli.truncate_name,
@@ -145,13 +233,18 @@ fn main() -> ExitCode {
         jj_symbol,
         git_symbol,
         cli.no_symbol,
+        cli.jj_format,
+        git_format,
         jj_flags,
         git_flags,
+        git_status,
+        palette_flags,
+        shell,
     );
 
     match cli.command.unwrap_or(Command::Prompt) {
         Command::Prompt => {
-            if let Some(output) = run_prompt(&cwd, &config) {
+            if let Some(output) = run_prompt(&cwd, &config, cli.format) {
                 print!("{output}");
                 ExitCode::SUCCESS
             } else {
@@ -174,7 +267,7 @@ fn main() -> ExitCode {
 
 /// Run prompt generation, returning None on error (silent fail for prompts)
 #[allow(unreachable_patterns)]
-fn run_prompt(cwd: &Path, config: &Config) -> Option<String> {
+fn run_prompt(cwd: &Path, config: &Config, format: OutputFormat) -> Option<String> {
     let result = detect::detect(cwd);
 
     match result.repo_type {
@@ -182,13 +275,19 @@ fn run_prompt(cwd: &Path, config: &Config) -> Option<String> {
             let repo_root = result.repo_root?;
             let info =
                 jj::collect(&repo_root, config.id_length, config.ancestor_bookmark_depth).ok()?;
-            Some(output::format_jj(&info, config))
+            Some(match format {
+                OutputFormat::Text => output::format_jj(&info, config),
+                OutputFormat::Json => output::to_json_jj(&info, config),
+            })
         }
-        #[cfg(feature = "git")]
+        #[cfg(any(feature = "git", feature = "gix"))]
         RepoType::Git => {
             let repo_root = result.repo_root?;
             let info = git::collect(&repo_root, config.id_length).ok()?;
-            Some(output::format_git(&info, config))
+            Some(match format {
+                OutputFormat::Text => output::format_git(&info, config),
+                OutputFormat::Json => output::to_json_git(&info, config),
+            })
         }
         RepoType::None => None,
         // Catch disabled variants
@@ -210,6 +309,8 @@ fn print_version() {
     let mut features = Vec::new();
     #[cfg(feature = "git")]
     features.push("git");
+    #[cfg(feature = "gix")]
+    features.push("gix");
 
     if features.is_empty() {
         println!("features: none");
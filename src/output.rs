@@ -1,190 +1,344 @@
 //! Output formatting for prompt strings
 
-#[cfg(feature = "git")]
+#[cfg(any(feature = "git", feature = "gix"))]
 use std::borrow::Cow;
-#[cfg(feature = "git")]
 use std::fmt::Write;
 
-use crate::color::{BLUE, BRIGHT_BLACK, BRIGHT_MAGENTA, GREEN, PURPLE, RED, RESET};
+use crate::color::{wrap_escape, ShellType, RESET};
 use crate::config::Config;
-#[cfg(feature = "git")]
+#[cfg(any(feature = "git", feature = "gix"))]
 use crate::git::GitInfo;
 use crate::jj::JjInfo;
+use crate::template::{self, Vars};
+use serde::Serialize;
 
-fn format_segment(text: &str, color: &str, show_color: bool) -> String {
+fn format_segment(text: &str, color: &str, show_color: bool, shell: ShellType) -> String {
     if show_color {
-        format!("{color}{text}{RESET}")
+        format!(
+            "{}{text}{}",
+            wrap_escape(color, shell),
+            wrap_escape(RESET, shell)
+        )
     } else {
         text.to_string()
     }
 }
 
 /// Format `change_id` with unique prefix highlighting (matching jj log style)
-/// Prefix is bright magenta, rest is gray
-fn format_change_id(change_id: &str, prefix_len: usize, show_prefix_color: bool) -> String {
+fn format_change_id(
+    change_id: &str,
+    prefix_len: usize,
+    show_prefix_color: bool,
+    highlight: &str,
+    rest_color: &str,
+    shell: ShellType,
+) -> String {
     if !show_prefix_color {
         return change_id.to_string();
     }
     let prefix_len = prefix_len.min(change_id.len());
     let prefix = &change_id[..prefix_len];
     let rest = &change_id[prefix_len..];
+    let (highlight, rest_color, reset) = (
+        wrap_escape(highlight, shell),
+        wrap_escape(rest_color, shell),
+        wrap_escape(RESET, shell),
+    );
     if rest.is_empty() {
-        format!("{BRIGHT_MAGENTA}{prefix}{RESET}")
+        format!("{highlight}{prefix}{reset}")
     } else {
-        format!("{BRIGHT_MAGENTA}{prefix}{RESET}{BRIGHT_BLACK}{rest}{RESET}")
+        format!("{highlight}{prefix}{reset}{rest_color}{rest}{reset}")
     }
 }
 
-/// Format JJ info as prompt string
-/// Pattern: `on {symbol}{change_id} ({bookmarks}) [{status}]`
-pub fn format_jj(info: &JjInfo, config: &Config) -> String {
-    let mut out = String::with_capacity(128);
+/// Build the colored bookmarks segment text (without the surrounding space),
+/// or `None` if bookmarks shouldn't be shown at all.
+fn jj_bookmarks_var(info: &JjInfo, config: &Config) -> Option<String> {
+    let display = &config.jj_display;
+    if !display.show_name || info.bookmarks.is_empty() {
+        return None;
+    }
+
+    let bookmark_strs: Vec<String> = info
+        .bookmarks
+        .iter()
+        .map(|(name, dist)| {
+            let truncated = config.truncate(name);
+            let conflicted = info
+                .conflicted_bookmarks
+                .iter()
+                .any(|conflicted_name| conflicted_name == name);
+            match (*dist > 0, conflicted) {
+                (true, true) => format!("{truncated}~{dist}!"),
+                (true, false) => format!("{truncated}~{dist}"),
+                (false, true) => format!("{truncated}!"),
+                (false, false) => truncated.into_owned(),
+            }
+        })
+        .collect();
+    let bookmarks_text = format!("({})", bookmark_strs.join(", "));
+    Some(format_segment(
+        &bookmarks_text,
+        &config.palette.bookmarks.escape(),
+        display.show_color,
+        config.shell,
+    ))
+}
+
+/// Build the colored status segment text (priority: ! > ⇔ > ⑂ > ? > ⇡N > ⇣N),
+/// or `None` if there's nothing to show.
+fn jj_status_var(info: &JjInfo, config: &Config) -> Option<String> {
     let display = &config.jj_display;
+    if !display.show_status {
+        return None;
+    }
+
+    let mut status = String::new();
+    if info.conflict {
+        status.push('!');
+    }
+    if info.divergent {
+        status.push('⇔');
+    }
+    if info.divergent_operations {
+        status.push('⑂');
+    }
+    if info.empty_desc {
+        status.push('?');
+    }
+    if info.ahead > 0 {
+        let _ = write!(status, "⇡{}", info.ahead);
+        if info.ahead_capped {
+            status.push('+');
+        }
+    }
+    if info.behind > 0 {
+        let _ = write!(status, "⇣{}", info.behind);
+        if info.behind_capped {
+            status.push('+');
+        }
+    }
 
-    // "on {symbol}" prefix
-    if display.show_prefix {
-        out.push_str("on ");
-        out.push_str(&format_segment(&config.jj_symbol, BLUE, display.show_color));
+    if status.is_empty() {
+        return None;
     }
+    let status_text = format!("[{status}]");
+    Some(format_segment(
+        &status_text,
+        &config.palette.status.escape(),
+        display.show_color,
+        config.shell,
+    ))
+}
 
-    // change_id with prefix coloring (controlled by show_id)
-    if display.show_id {
-        let use_prefix_color = display.show_color && display.show_prefix_color;
-        if use_prefix_color {
-            out.push_str(&format_change_id(
+/// Format JJ info as a prompt string, rendering `config.jj_format` against
+/// `info`. Default template: `(on $symbol)$change_id( $bookmarks)( $status)`
+pub fn format_jj(info: &JjInfo, config: &Config) -> String {
+    let display = &config.jj_display;
+    let palette = &config.palette;
+
+    let symbol = display.show_prefix.then(|| {
+        format_segment(
+            &config.jj_symbol,
+            &palette.symbol.escape(),
+            display.show_color,
+            config.shell,
+        )
+    });
+
+    let change_id = display.show_id.then(|| {
+        if display.show_color && display.show_prefix_color {
+            format_change_id(
                 &info.change_id,
                 info.change_id_prefix_len,
                 true,
-            ));
+                &palette.prefix_highlight.escape(),
+                &palette.prefix_rest.escape(),
+                config.shell,
+            )
         } else {
-            out.push_str(&format_segment(&info.change_id, PURPLE, display.show_color));
+            format_segment(
+                &info.change_id,
+                &palette.name.escape(),
+                display.show_color,
+                config.shell,
+            )
         }
+    });
+
+    let vars: Vars = [
+        ("symbol", symbol),
+        ("change_id", change_id),
+        ("bookmarks", jj_bookmarks_var(info, config)),
+        ("status", jj_status_var(info, config)),
+    ]
+    .into_iter()
+    .collect();
+
+    template::render(&config.jj_format, &vars)
+}
+
+/// Build the colored commit-id segment text, or `None` if hidden.
+#[cfg(any(feature = "git", feature = "gix"))]
+fn git_commit_var(info: &GitInfo, config: &Config) -> Option<String> {
+    let display = &config.git_display;
+    if !display.show_id {
+        return None;
     }
+    let id_text = format!("({})", &info.head_short);
+    Some(format_segment(
+        &id_text,
+        &config.palette.id.escape(),
+        display.show_color,
+        config.shell,
+    ))
+}
 
-    // Bookmarks in parentheses (controlled by show_name - they're names/labels)
-    if display.show_name && !info.bookmarks.is_empty() {
-        if !out.is_empty() {
-            out.push(' ');
-        }
+/// Build the colored status segment text (order: = > + > » > ! > ? > ✘ > $,
+/// followed by ahead/behind or the collapsed `⇕` diverged glyph), or `None`
+/// if there's nothing to show. Falls back to `git_status.clean_symbol` when
+/// configured and the repo is synced with no changes.
+#[cfg(any(feature = "git", feature = "gix"))]
+fn git_status_var(info: &GitInfo, config: &Config) -> Option<String> {
+    let display = &config.git_display;
+    if !display.show_status {
+        return None;
+    }
 
-        let bookmark_strs: Vec<String> = info
-            .bookmarks
-            .iter()
-            .map(|(name, dist)| {
-                let truncated = config.truncate(name);
-                if *dist > 0 {
-                    format!("{truncated}~{dist}")
-                } else {
-                    truncated.into_owned()
-                }
-            })
-            .collect();
-        let bookmarks_text = format!("({})", bookmark_strs.join(", "));
-        out.push_str(&format_segment(&bookmarks_text, GREEN, display.show_color));
-    }
-
-    // Status indicators in red (priority: ! > ⇔ > ? > ⇡)
-    if display.show_status {
-        let mut status = String::new();
-        if info.conflict {
-            status.push('!');
-        }
-        if info.divergent {
-            status.push('⇔');
-        }
-        if info.empty_desc {
-            status.push('?');
+    let mut status = String::new();
+    if info.conflicted > 0 {
+        status.push('=');
+    }
+    if info.staged > 0 {
+        status.push('+');
+    }
+    if info.renamed > 0 {
+        status.push('»');
+    }
+    if info.modified > 0 {
+        status.push('!');
+    }
+    if info.untracked > 0 {
+        status.push('?');
+    }
+    if info.deleted > 0 {
+        status.push('✘');
+    }
+    if info.stashed > 0 {
+        status.push('$');
+    }
+
+    let git_status = &config.git_status;
+    if info.ahead > 0 && info.behind > 0 && git_status.collapse_diverged {
+        status.push('⇕');
+        if git_status.diverged_show_counts {
+            let _ = write!(status, "⇡{}⇣{}", info.ahead, info.behind);
         }
-        if info.has_remote && !info.is_synced {
-            status.push('⇡');
+    } else {
+        if info.ahead > 0 {
+            let _ = write!(status, "⇡{}", info.ahead);
         }
-
-        if !status.is_empty() {
-            if !out.is_empty() {
-                out.push(' ');
-            }
-            let status_text = format!("[{}]", &status);
-            out.push_str(&format_segment(&status_text, RED, display.show_color));
+        if info.behind > 0 {
+            let _ = write!(status, "⇣{}", info.behind);
         }
     }
 
-    out
+    if status.is_empty() {
+        return git_status.clean_symbol.as_ref().map(|symbol| {
+            format_segment(
+                &format!("[{symbol}]"),
+                &config.palette.status.escape(),
+                display.show_color,
+                config.shell,
+            )
+        });
+    }
+    let status_text = format!("[{status}]");
+    Some(format_segment(
+        &status_text,
+        &config.palette.status.escape(),
+        display.show_color,
+        config.shell,
+    ))
 }
 
-/// Format Git info as prompt string
-/// Pattern: `on {symbol}{name} ({id}) [{status}]`
-#[cfg(feature = "git")]
+/// Format Git info as a prompt string, rendering `config.git_format` against
+/// `info`. Default template: `(on $symbol)$branch( $commit)( $status)`
+#[cfg(any(feature = "git", feature = "gix"))]
 pub fn format_git(info: &GitInfo, config: &Config) -> String {
-    let mut out = String::with_capacity(128);
     let display = &config.git_display;
+    let palette = &config.palette;
 
-    // "on {symbol}" prefix
-    if display.show_prefix {
-        out.push_str("on ");
-        out.push_str(&format_segment(
+    let symbol = display.show_prefix.then(|| {
+        format_segment(
             &config.git_symbol,
-            BLUE,
+            &palette.symbol.escape(),
             display.show_color,
-        ));
-    }
+            config.shell,
+        )
+    });
 
-    // Name in purple (branch or HEAD)
-    if display.show_name {
+    let branch = display.show_name.then(|| {
         let name: Cow<str> = info
             .branch
             .as_ref()
             .map_or(Cow::Borrowed("HEAD"), |b| config.truncate(b));
-        out.push_str(&format_segment(&name, PURPLE, display.show_color));
-    }
-
-    // ID in green
-    if display.show_id {
-        if !out.is_empty() {
-            out.push(' ');
-        }
-        let id_text = format!("({})", &info.head_short);
-        out.push_str(&format_segment(&id_text, GREEN, display.show_color));
-    }
-
-    // Status indicators in red
-    if display.show_status {
-        let mut status = String::new();
+        format_segment(
+            &name,
+            &palette.name.escape(),
+            display.show_color,
+            config.shell,
+        )
+    });
+
+    let vars: Vars = [
+        ("symbol", symbol),
+        ("branch", branch),
+        ("commit", git_commit_var(info, config)),
+        ("status", git_status_var(info, config)),
+    ]
+    .into_iter()
+    .collect();
+
+    template::render(&config.git_format, &vars)
+}
 
-        // File status (order: = > + > ! > ? > ✘)
-        if info.conflicted > 0 {
-            status.push('=');
-        }
-        if info.staged > 0 {
-            status.push('+');
-        }
-        if info.modified > 0 {
-            status.push('!');
-        }
-        if info.untracked > 0 {
-            status.push('?');
-        }
-        if info.deleted > 0 {
-            status.push('✘');
-        }
+/// JJ info plus the resolved symbol, as exposed by `--format json`.
+/// Field names/shape are a stable contract for downstream tools - don't
+/// rename fields on `JjInfo`/`JjJson` without a good reason.
+#[derive(Serialize)]
+struct JjJson<'a> {
+    symbol: &'a str,
+    #[serde(flatten)]
+    info: &'a JjInfo,
+}
 
-        // Ahead/behind
-        if info.ahead > 0 {
-            let _ = write!(status, "⇡{}", info.ahead);
-        }
-        if info.behind > 0 {
-            let _ = write!(status, "⇣{}", info.behind);
-        }
+/// Serialize JJ info as a stable JSON object for `--format json`
+pub fn to_json_jj(info: &JjInfo, config: &Config) -> String {
+    let payload = JjJson {
+        symbol: &config.jj_symbol,
+        info,
+    };
+    serde_json::to_string(&payload).unwrap_or_default()
+}
 
-        if !status.is_empty() {
-            if !out.is_empty() {
-                out.push(' ');
-            }
-            let status_text = format!("[{}]", &status);
-            out.push_str(&format_segment(&status_text, RED, display.show_color));
-        }
-    }
+/// Git info plus the resolved symbol, as exposed by `--format json`.
+#[cfg(any(feature = "git", feature = "gix"))]
+#[derive(Serialize)]
+struct GitJson<'a> {
+    symbol: &'a str,
+    #[serde(flatten)]
+    info: &'a GitInfo,
+}
 
-    out
+/// Serialize Git info as a stable JSON object for `--format json`
+#[cfg(any(feature = "git", feature = "gix"))]
+pub fn to_json_git(info: &GitInfo, config: &Config) -> String {
+    let payload = GitJson {
+        symbol: &config.git_symbol,
+        info,
+    };
+    serde_json::to_string(&payload).unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -192,10 +346,11 @@ mod tests {
     use super::*;
     use std::borrow::Cow;
 
-    #[cfg(feature = "git")]
+    use crate::color::{BLUE, BRIGHT_BLACK, BRIGHT_MAGENTA, GREEN, PURPLE, RED};
+    #[cfg(any(feature = "git", feature = "gix"))]
     use crate::config::DEFAULT_GIT_SYMBOL;
     use crate::config::DEFAULT_JJ_SYMBOL;
-    use crate::config::DisplayConfig;
+    use crate::config::{DisplayConfig, GitStatusConfig, Palette};
 
     #[allow(dead_code)]
     fn default_config() -> Config {
@@ -211,7 +366,12 @@ mod tests {
             jj_symbol: Cow::Borrowed(""),
             git_symbol: Cow::Borrowed(""),
             jj_display: DisplayConfig::all_visible(),
+            jj_format: Cow::Borrowed(crate::config::DEFAULT_JJ_FORMAT),
+            git_format: Cow::Borrowed(crate::config::DEFAULT_GIT_FORMAT),
             git_display: DisplayConfig::all_visible(),
+            git_status: GitStatusConfig::default(),
+            palette: Palette::default(),
+            shell: ShellType::Other,
         }
     }
 
@@ -226,6 +386,12 @@ mod tests {
             divergent: false,
             has_remote: true,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &no_symbol_config()),
@@ -247,6 +413,12 @@ mod tests {
             divergent: false,
             has_remote: false,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &no_symbol_config()),
@@ -267,6 +439,12 @@ mod tests {
             divergent: false,
             has_remote: true,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &default_config()),
@@ -285,7 +463,12 @@ mod tests {
             jj_symbol: Cow::Borrowed(""),
             git_symbol: Cow::Borrowed(""),
             jj_display: DisplayConfig::all_visible(),
+            jj_format: Cow::Borrowed(crate::config::DEFAULT_JJ_FORMAT),
+            git_format: Cow::Borrowed(crate::config::DEFAULT_GIT_FORMAT),
             git_display: DisplayConfig::all_visible(),
+            git_status: GitStatusConfig::default(),
+            palette: Palette::default(),
+            shell: ShellType::Other,
         };
         let info = JjInfo {
             change_id: "yzxv1234".into(),
@@ -296,6 +479,12 @@ mod tests {
             divergent: false,
             has_remote: false,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &config),
@@ -316,6 +505,12 @@ mod tests {
             divergent: false,
             has_remote: true,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &no_symbol_config()),
@@ -336,6 +531,12 @@ mod tests {
             divergent: false,
             has_remote: false,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &no_symbol_config()),
@@ -343,6 +544,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_jj_format_divergent_operations() {
+        let info = JjInfo {
+            change_id: "yzxv1234".into(),
+            change_id_prefix_len: 4,
+            bookmarks: vec![],
+            empty_desc: false,
+            conflict: false,
+            divergent: false,
+            has_remote: false,
+            is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: true,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
+        };
+        assert_eq!(
+            format_jj(&info, &no_symbol_config()),
+            format!(
+                "on {BLUE}{RESET}{BRIGHT_MAGENTA}yzxv{RESET}{BRIGHT_BLACK}1234{RESET} {RED}[⑂]{RESET}"
+            )
+        );
+    }
+
     #[test]
     fn test_jj_format_multiple_bookmarks() {
         let info = JjInfo {
@@ -354,6 +581,12 @@ mod tests {
             divergent: false,
             has_remote: false,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &no_symbol_config()),
@@ -374,6 +607,12 @@ mod tests {
             divergent: false,
             has_remote: true,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         let config = Config {
             truncate_name: 0,
@@ -389,7 +628,12 @@ mod tests {
                 show_color: false,
                 show_prefix_color: true,
             },
+            jj_format: Cow::Borrowed(crate::config::DEFAULT_JJ_FORMAT),
+            git_format: Cow::Borrowed(crate::config::DEFAULT_GIT_FORMAT),
             git_display: DisplayConfig::all_visible(),
+            git_status: GitStatusConfig::default(),
+            palette: Palette::default(),
+            shell: ShellType::Other,
         };
         assert_eq!(format_jj(&info, &config), "on 󱗆 yzxv1234 (main)");
     }
@@ -405,6 +649,12 @@ mod tests {
             divergent: false,
             has_remote: false,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         let config = Config {
             truncate_name: 0,
@@ -420,7 +670,12 @@ mod tests {
                 show_color: true,
                 show_prefix_color: true,
             },
+            jj_format: Cow::Borrowed(crate::config::DEFAULT_JJ_FORMAT),
+            git_format: Cow::Borrowed(crate::config::DEFAULT_GIT_FORMAT),
             git_display: DisplayConfig::all_visible(),
+            git_status: GitStatusConfig::default(),
+            palette: Palette::default(),
+            shell: ShellType::Other,
         };
         // --no-jj-id hides change_id, shows only bookmarks
         assert_eq!(
@@ -440,6 +695,12 @@ mod tests {
             divergent: false,
             has_remote: false,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         let config = Config {
             truncate_name: 0,
@@ -455,7 +716,12 @@ mod tests {
                 show_color: true,
                 show_prefix_color: true,
             },
+            jj_format: Cow::Borrowed(crate::config::DEFAULT_JJ_FORMAT),
+            git_format: Cow::Borrowed(crate::config::DEFAULT_GIT_FORMAT),
             git_display: DisplayConfig::all_visible(),
+            git_status: GitStatusConfig::default(),
+            palette: Palette::default(),
+            shell: ShellType::Other,
         };
         // --no-jj-name hides bookmarks, shows only change_id with prefix coloring
         assert_eq!(
@@ -477,6 +743,12 @@ mod tests {
             divergent: false,
             has_remote: false,
             is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
         };
         assert_eq!(
             format_jj(&info, &no_symbol_config()),
@@ -486,7 +758,7 @@ mod tests {
         );
     }
 
-    #[cfg(feature = "git")]
+    #[cfg(any(feature = "git", feature = "gix"))]
     #[test]
     fn test_git_format_clean() {
         let info = GitInfo {
@@ -497,8 +769,10 @@ mod tests {
             untracked: 0,
             deleted: 0,
             conflicted: 0,
+            renamed: 0,
             ahead: 0,
             behind: 0,
+            stashed: 0,
         };
         assert_eq!(
             format_git(&info, &no_symbol_config()),
@@ -506,7 +780,7 @@ mod tests {
         );
     }
 
-    #[cfg(feature = "git")]
+    #[cfg(any(feature = "git", feature = "gix"))]
     #[test]
     fn test_git_format_dirty() {
         let info = GitInfo {
@@ -517,8 +791,10 @@ mod tests {
             untracked: 1,
             deleted: 0,
             conflicted: 0,
+            renamed: 0,
             ahead: 2,
             behind: 1,
+            stashed: 0,
         };
         assert_eq!(
             format_git(&info, &no_symbol_config()),
@@ -528,7 +804,7 @@ mod tests {
         );
     }
 
-    #[cfg(feature = "git")]
+    #[cfg(any(feature = "git", feature = "gix"))]
     #[test]
     fn test_git_format_with_symbol() {
         let info = GitInfo {
@@ -539,8 +815,10 @@ mod tests {
             untracked: 0,
             deleted: 0,
             conflicted: 0,
+            renamed: 0,
             ahead: 0,
             behind: 0,
+            stashed: 0,
         };
         assert_eq!(
             format_git(&info, &default_config()),
@@ -549,4 +827,60 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_to_json_jj_shape() {
+        let info = JjInfo {
+            change_id: "yzxv1234".into(),
+            change_id_prefix_len: 4,
+            bookmarks: vec![("main".into(), 0)],
+            empty_desc: false,
+            conflict: false,
+            divergent: false,
+            has_remote: true,
+            is_synced: true,
+            conflicted_bookmarks: vec![],
+            divergent_operations: false,
+            ahead: 0,
+            behind: 0,
+            ahead_capped: false,
+            behind_capped: false,
+        };
+        assert_eq!(
+            to_json_jj(&info, &default_config()),
+            format!(
+                "{{\"symbol\":\"{DEFAULT_JJ_SYMBOL}\",\"change_id\":\"yzxv1234\",\"change_id_prefix_len\":4,\
+                 \"bookmarks\":[[\"main\",0]],\"empty_desc\":false,\"conflict\":false,\"divergent\":false,\
+                 \"has_remote\":true,\"is_synced\":true,\"conflicted_bookmarks\":[],\
+                 \"divergent_operations\":false,\"ahead\":0,\"behind\":0,\"ahead_capped\":false,\
+                 \"behind_capped\":false}}"
+            )
+        );
+    }
+
+    #[cfg(any(feature = "git", feature = "gix"))]
+    #[test]
+    fn test_to_json_git_shape() {
+        let info = GitInfo {
+            branch: Some("main".into()),
+            head_short: "a3b4c5d".into(),
+            staged: 0,
+            modified: 0,
+            untracked: 0,
+            deleted: 0,
+            conflicted: 0,
+            renamed: 0,
+            ahead: 0,
+            behind: 0,
+            stashed: 0,
+        };
+        assert_eq!(
+            to_json_git(&info, &default_config()),
+            format!(
+                "{{\"symbol\":\"{DEFAULT_GIT_SYMBOL}\",\"branch\":\"main\",\"head_short\":\"a3b4c5d\",\
+                 \"staged\":0,\"modified\":0,\"untracked\":0,\"deleted\":0,\"conflicted\":0,\"renamed\":0,\
+                 \"ahead\":0,\"behind\":0,\"stashed\":0}}"
+            )
+        );
+    }
 }
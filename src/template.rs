@@ -0,0 +1,210 @@
+//! Small template engine for prompt format strings
+//!
+//! A template is literal text, `$name` variables, and `(...)` groups. A
+//! group renders (including its literal text) only if at least one variable
+//! inside it is present; this lets a format string reorder or drop segments
+//! without the per-flag wiring `format_jj`/`format_git` used to need. The
+//! leading `"on "` text itself lives inside a group for exactly this reason:
+//! it should disappear along with `$symbol` when the prefix is hidden.
+
+use std::collections::HashMap;
+
+/// Resolved variables for a single render. `None` means the variable is not
+/// shown (hidden by a display flag or empty), collapsing any group it's in.
+pub type Vars<'a> = HashMap<&'a str, Option<String>>;
+
+#[derive(Debug, Clone)]
+enum Token {
+    Literal(String),
+    Var(String),
+    Group(Vec<Token>),
+}
+
+/// Render `template` against `vars`, trimming a leading separator left
+/// behind when the first segment in the template collapses.
+#[must_use]
+pub fn render(template: &str, vars: &Vars) -> String {
+    let chars: Vec<char> = template.chars().collect();
+    let mut pos = 0;
+    let tokens = parse(&chars, &mut pos, false);
+    let (text, _present) = render_tokens(&tokens, vars);
+    text.trim_start().to_string()
+}
+
+/// Parse into a token tree. An unmatched `(` is closed implicitly at the end
+/// of the template, so everything after it forms one group under the usual
+/// collapse rule - malformed input degrades gracefully instead of producing
+/// no prompt at all.
+fn parse(chars: &[char], pos: &mut usize, in_group: bool) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    while *pos < chars.len() {
+        let c = chars[*pos];
+        if in_group && c == ')' {
+            *pos += 1;
+            break;
+        }
+        if c == '(' {
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+            *pos += 1;
+            tokens.push(Token::Group(parse(chars, pos, true)));
+            continue;
+        }
+        if c == '$' {
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+            *pos += 1;
+            let mut name = String::new();
+            while *pos < chars.len() && (chars[*pos].is_ascii_alphanumeric() || chars[*pos] == '_')
+            {
+                name.push(chars[*pos]);
+                *pos += 1;
+            }
+            tokens.push(Token::Var(name));
+            continue;
+        }
+        literal.push(c);
+        *pos += 1;
+    }
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    tokens
+}
+
+/// Render a token sequence, returning the text and whether any variable in
+/// it was present (used by the parent group to decide whether to keep it).
+fn render_tokens(tokens: &[Token], vars: &Vars) -> (String, bool) {
+    let mut out = String::new();
+    let mut any_present = false;
+    for token in tokens {
+        match token {
+            Token::Literal(text) => out.push_str(text),
+            Token::Var(name) => {
+                if let Some(Some(value)) = vars.get(name.as_str()) {
+                    out.push_str(value);
+                    any_present = true;
+                }
+            }
+            Token::Group(inner) => {
+                let (inner_text, inner_present) = render_tokens(inner, vars);
+                if inner_present {
+                    out.push_str(&inner_text);
+                    any_present = true;
+                }
+            }
+        }
+    }
+    (out, any_present)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&'static str, Option<&str>)]) -> Vars<'static> {
+        pairs
+            .iter()
+            .map(|(k, v)| (*k, v.map(String::from)))
+            .collect()
+    }
+
+    #[test]
+    fn test_literal_only() {
+        assert_eq!(render("hello", &vars(&[])), "hello");
+    }
+
+    #[test]
+    fn test_var_substitution() {
+        let v = vars(&[("name", Some("world"))]);
+        assert_eq!(render("hello $name", &v), "hello world");
+    }
+
+    #[test]
+    fn test_missing_var_renders_empty() {
+        let v = vars(&[("name", None)]);
+        assert_eq!(render("hello $name", &v), "hello ");
+    }
+
+    #[test]
+    fn test_group_collapses_when_var_absent() {
+        let v = vars(&[("change_id", Some("abcd")), ("status", None)]);
+        assert_eq!(render("$change_id( $status)", &v), "abcd");
+    }
+
+    #[test]
+    fn test_group_kept_when_var_present() {
+        let v = vars(&[("change_id", Some("abcd")), ("status", Some("[!]"))]);
+        assert_eq!(render("$change_id( $status)", &v), "abcd [!]");
+    }
+
+    #[test]
+    fn test_group_with_literal_prefix_collapses_whole_group() {
+        // "on $symbol" disappears entirely when $symbol is hidden, not just
+        // the variable - the "on " literal lives inside the group too.
+        let v = vars(&[("symbol", None), ("change_id", Some("abcd"))]);
+        assert_eq!(render("(on $symbol)$change_id", &v), "abcd");
+    }
+
+    #[test]
+    fn test_leading_separator_trimmed_when_first_group_collapses() {
+        let v = vars(&[("symbol", None), ("change_id", Some("abcd"))]);
+        // No leading space left behind once the "on $symbol" group is gone
+        assert_eq!(render("(on $symbol)$change_id", &v), "abcd");
+        assert!(!render("(on $symbol)$change_id", &v).starts_with(' '));
+    }
+
+    #[test]
+    fn test_present_but_empty_string_still_counts_as_present() {
+        // Presence (Some vs None), not string emptiness, decides collapsing -
+        // an empty-but-colored symbol still keeps its group.
+        let v = vars(&[("symbol", Some(String::new()))]);
+        assert_eq!(render("(on $symbol)", &v), "on ");
+    }
+
+    #[test]
+    fn test_unknown_var_treated_as_absent() {
+        let v = vars(&[]);
+        assert_eq!(render("($foo)bar", &v), "bar");
+    }
+
+    #[test]
+    fn test_nested_groups() {
+        let v = vars(&[("a", Some("A")), ("b", None)]);
+        assert_eq!(render("$a(( $b))", &v), "A");
+        let v2 = vars(&[("a", Some("A")), ("b", Some("B"))]);
+        assert_eq!(render("$a(( $b))", &v2), "A B");
+    }
+
+    #[test]
+    fn test_unmatched_open_paren_degrades_gracefully() {
+        // No closing ')' before EOF: the dangling group still renders under
+        // the usual collapse rule (kept here since $shown is present)
+        // instead of erroring out the whole template.
+        let v = vars(&[("name", Some("x")), ("shown", Some("y"))]);
+        assert_eq!(render("$name(extra $shown", &v), "xextra y");
+    }
+
+    #[test]
+    fn test_unmatched_open_paren_with_no_var_collapses() {
+        let v = vars(&[("name", Some("x"))]);
+        assert_eq!(render("$name(extra", &v), "x");
+    }
+
+    #[test]
+    fn test_default_jj_format_matches_original_layout() {
+        let v = vars(&[
+            ("symbol", Some("X")),
+            ("change_id", Some("abcd1234")),
+            ("bookmarks", Some("(main)")),
+            ("status", None),
+        ]);
+        assert_eq!(
+            render("(on $symbol)$change_id( $bookmarks)( $status)", &v),
+            "on Xabcd1234 (main)"
+        );
+    }
+}